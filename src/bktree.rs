@@ -0,0 +1,130 @@
+//! A BK-tree index for approximate nearest-neighbor search over values whose
+//! pairwise distance (such as [`Tlshx::diff`](crate::tlshx::Tlshx::diff)) obeys,
+//! or nearly obeys, the triangle inequality.
+//!
+//! Scanning a whole corpus with [`diff`](crate::tlshx::Tlshx::diff) to answer
+//! "which hashes are within distance `t` of this one?" is `O(n)` per query.
+//! A [`BkTree`] amortizes that cost: each node's children are keyed by their
+//! exact distance to the parent, so a range query only has to descend into
+//! children whose edge label could plausibly be within `t` of the query,
+//! pruning the rest via the triangle inequality.
+//!
+//! Because TLSH's `diff` is only an approximate metric, two hashes a
+//! hair's-breadth apart can occasionally violate the triangle inequality by a
+//! small amount. Callers chasing exact recall near the threshold should pad
+//! `t` with a small slack margin (a few distance units is usually enough) to
+//! avoid pruning away a true match.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// A BK-tree keyed on a caller-supplied distance function.
+///
+/// `F` is typically a closure wrapping [`Tlshx::diff`](crate::tlshx::Tlshx::diff)
+/// with a fixed `len_diff` choice, e.g. `|a, b| a.diff(b, false)`.
+pub struct BkTree<T, F>
+where
+    F: Fn(&T, &T) -> i32,
+{
+    root: Option<Box<Node<T>>>,
+    distance: F,
+}
+
+struct Node<T> {
+    value: T,
+    // Children keyed by their exact distance from this node.
+    children: Vec<(i32, Box<Node<T>>)>,
+}
+
+impl<T, F> BkTree<T, F>
+where
+    F: Fn(&T, &T) -> i32,
+{
+    /// Create an empty BK-tree that compares values with `distance`.
+    pub fn new(distance: F) -> Self {
+        Self {
+            root: None,
+            distance,
+        }
+    }
+
+    /// Insert a value into the tree.
+    pub fn insert(&mut self, value: T) {
+        let Some(root) = &mut self.root else {
+            self.root = Some(Box::new(Node {
+                value,
+                children: Vec::new(),
+            }));
+            return;
+        };
+
+        let mut node = root.as_mut();
+        loop {
+            let d = (self.distance)(&node.value, &value);
+            match node.children.iter().position(|(edge, _)| *edge == d) {
+                Some(i) => node = node.children[i].1.as_mut(),
+                None => {
+                    node.children.push((
+                        d,
+                        Box::new(Node {
+                            value,
+                            children: Vec::new(),
+                        }),
+                    ));
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Return every value within distance `t` of `query`.
+    pub fn query(&self, query: &T, t: i32) -> Vec<&T> {
+        let mut results = Vec::new();
+        if let Some(root) = &self.root {
+            self.query_node(root, query, t, &mut results);
+        }
+        results
+    }
+
+    fn query_node<'a>(&self, node: &'a Node<T>, query: &T, t: i32, results: &mut Vec<&'a T>) {
+        let d = (self.distance)(&node.value, query);
+        if d <= t {
+            results.push(&node.value);
+        }
+        for (edge, child) in &node.children {
+            // Triangle inequality: any match under `child` is within `t` of
+            // `query` only if the edge label is within `t` of `d`, this node's
+            // distance to `query`. Children outside that band can be skipped entirely.
+            if (edge - d).abs() <= t {
+                self.query_node(child, query, t, results);
+            }
+        }
+    }
+
+    /// Return the value closest to `query` and its distance, or `None` if the
+    /// tree is empty.
+    pub fn nearest(&self, query: &T) -> Option<(&T, i32)> {
+        let root = self.root.as_ref()?;
+        let mut best: Option<(&T, i32)> = None;
+        self.nearest_node(root, query, &mut best);
+        best
+    }
+
+    fn nearest_node<'a>(&self, node: &'a Node<T>, query: &T, best: &mut Option<(&'a T, i32)>) {
+        let d = (self.distance)(&node.value, query);
+        let improves = match best {
+            Some((_, best_d)) => d < *best_d,
+            None => true,
+        };
+        if improves {
+            *best = Some((&node.value, d));
+        }
+
+        let radius = best.map_or(i32::MAX, |(_, best_d)| best_d);
+        for (edge, child) in &node.children {
+            if (edge - d).abs() <= radius {
+                self.nearest_node(child, query, best);
+            }
+        }
+    }
+}
@@ -9,6 +9,25 @@ const SLIDING_WND_SIZE: usize = 5;
 
 const RNG_SIZE: usize = SLIDING_WND_SIZE;
 
+/// Chunk size used by [`TlshxBuilder::update_parallel`] to split work across threads.
+#[cfg(feature = "threaded")]
+const THREAD_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Default "buckets must be non-zero" acceptance ratio (out of 100) for
+/// [`TlshxBuilder::try_build`], absent a [`set_min_nonzero_percent`]
+/// override. `EFF_BUCKETS == 48` is a special case: upstream TLSH relaxes
+/// the ratio to 40% for that configuration, since it has noticeably fewer
+/// buckets to fill.
+///
+/// [`set_min_nonzero_percent`]: TlshxBuilder::set_min_nonzero_percent
+const fn default_min_nonzero_percent(eff_buckets: usize) -> u8 {
+    if eff_buckets == 48 {
+        40
+    } else {
+        50
+    }
+}
+
 /// Builder object, processing streams of bytes to generate [`Tlshx`] objects.
 ///
 /// You should never provide your own values for the generics, but instead use the pre-configured
@@ -24,6 +43,7 @@ pub struct TlshxBuilder<
     slide_window: [u8; SLIDING_WND_SIZE],
     checksum: [u8; TLSH_CHECKSUM_LEN],
     data_len: usize,
+    min_nonzero_percent: Option<u8>,
 }
 
 impl<
@@ -62,6 +82,7 @@ impl<
             slide_window: [0; SLIDING_WND_SIZE],
             checksum: [0; TLSH_CHECKSUM_LEN],
             data_len: 0,
+            min_nonzero_percent: None,
         }
     }
 
@@ -88,7 +109,7 @@ impl<
 
     /// Add bytes into the builder.
     pub fn update(&mut self, data: &[u8]) {
-        // TODO: TLSH_OPTION_THREADED | TLSH_OPTION_PRIVATE
+        // TODO: TLSH_OPTION_PRIVATE
 
         let mut j = self.data_len % RNG_SIZE;
         let mut fed_len = self.data_len;
@@ -170,28 +191,253 @@ impl<
         self.data_len += data.len();
     }
 
+    /// Add bytes into the builder using a parallel bucket-accumulation pass.
+    ///
+    /// Requires the `threaded` feature. `data` is split into fixed-size
+    /// chunks processed concurrently; each chunk is seeded with the
+    /// trailing `RNG_SIZE - 1` bytes of its predecessor so the sliding
+    /// window and triple selections stay continuous across chunk
+    /// boundaries. The per-chunk bucket counts are then summed elementwise,
+    /// which is valid since addition doesn't care about ordering.
+    ///
+    /// The checksum folds over the stream byte-by-byte and can't be
+    /// parallelized, so it's always computed with a serial pass; aside from
+    /// that, output is identical to [`update`](Self::update).
+    ///
+    /// This expects to be called once on a freshly created builder with the
+    /// whole input, rather than interleaved with [`update`](Self::update).
+    #[cfg(feature = "threaded")]
+    pub fn update_parallel(&mut self, data: &[u8]) {
+        use rayon::prelude::*;
+
+        debug_assert_eq!(self.data_len, 0, "update_parallel expects a fresh builder");
+
+        if data.len() < 2 * THREAD_CHUNK_SIZE {
+            self.update(data);
+            return;
+        }
+
+        self.update_checksum_only(data);
+
+        let partial_buckets = data
+            .par_chunks(THREAD_CHUNK_SIZE)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let offset = i * THREAD_CHUNK_SIZE;
+                let ctx_start = offset.saturating_sub(RNG_SIZE - 1);
+                Self::accumulate_bucket(&data[ctx_start..offset], chunk)
+            })
+            .reduce(
+                || [0u32; BUCKETS],
+                |mut acc, part| {
+                    for (a, b) in acc.iter_mut().zip(part.iter()) {
+                        *a += b;
+                    }
+                    acc
+                },
+            );
+
+        for (a, b) in self.a_bucket.iter_mut().zip(partial_buckets.iter()) {
+            *a += b;
+        }
+
+        self.data_len += data.len();
+    }
+
+    /// Fold `data` into `self.checksum`, leaving `self.a_bucket` untouched.
+    ///
+    /// Mirrors the window bookkeeping in [`update`](Self::update) so the
+    /// builder's `slide_window` stays consistent for a later call to
+    /// [`update`](Self::update), but skips the bucket increments, which
+    /// [`update_parallel`](Self::update_parallel) computes separately.
+    /// `data_len` is not advanced here; the caller adds `data.len()`.
+    #[cfg(feature = "threaded")]
+    fn update_checksum_only(&mut self, data: &[u8]) {
+        let mut j = self.data_len % RNG_SIZE;
+        let mut fed_len = self.data_len;
+
+        for b in data {
+            self.slide_window[j] = *b;
+
+            if fed_len >= 4 {
+                let j_1 = (j + RNG_SIZE - 1) % RNG_SIZE;
+
+                for k in 0..TLSH_CHECKSUM_LEN {
+                    if k == 0 {
+                        self.checksum[k] = fast_b_mapping::<EFF_BUCKETS>(
+                            1,
+                            self.slide_window[j],
+                            self.slide_window[j_1],
+                            self.checksum[k],
+                        );
+                    } else {
+                        self.checksum[k] = b_mapping(
+                            self.checksum[k - 1],
+                            self.slide_window[j],
+                            self.slide_window[j_1],
+                            self.checksum[k],
+                        );
+                    }
+                }
+            }
+            fed_len += 1;
+            j = (j + 1) % RNG_SIZE;
+        }
+    }
+
+    /// Compute bucket hits for `chunk`, seeded with up to `RNG_SIZE - 1`
+    /// bytes of preceding context so the first few triples see the same
+    /// bytes they would in a serial pass over `context` followed by `chunk`.
+    #[cfg(feature = "threaded")]
+    fn accumulate_bucket(context: &[u8], chunk: &[u8]) -> [u32; BUCKETS] {
+        let mut a_bucket = [0u32; BUCKETS];
+        let mut slide_window = [0u8; SLIDING_WND_SIZE];
+        let mut j = 0;
+        let mut fed_len = 0;
+
+        for b in context.iter().chain(chunk.iter()) {
+            slide_window[j] = *b;
+
+            if fed_len >= 4 && fed_len >= context.len() {
+                let j_1 = (j + RNG_SIZE - 1) % RNG_SIZE;
+                let j_2 = (j + RNG_SIZE - 2) % RNG_SIZE;
+                let j_3 = (j + RNG_SIZE - 3) % RNG_SIZE;
+                let j_4 = (j + RNG_SIZE - 4) % RNG_SIZE;
+
+                let r = fast_b_mapping::<EFF_BUCKETS>(
+                    49,
+                    slide_window[j],
+                    slide_window[j_1],
+                    slide_window[j_2],
+                );
+                a_bucket[usize::from(r)] += 1;
+                let r = fast_b_mapping::<EFF_BUCKETS>(
+                    12,
+                    slide_window[j],
+                    slide_window[j_1],
+                    slide_window[j_3],
+                );
+                a_bucket[usize::from(r)] += 1;
+                let r = fast_b_mapping::<EFF_BUCKETS>(
+                    178,
+                    slide_window[j],
+                    slide_window[j_2],
+                    slide_window[j_3],
+                );
+                a_bucket[usize::from(r)] += 1;
+                let r = fast_b_mapping::<EFF_BUCKETS>(
+                    166,
+                    slide_window[j],
+                    slide_window[j_2],
+                    slide_window[j_4],
+                );
+                a_bucket[usize::from(r)] += 1;
+                let r = fast_b_mapping::<EFF_BUCKETS>(
+                    84,
+                    slide_window[j],
+                    slide_window[j_1],
+                    slide_window[j_4],
+                );
+                a_bucket[usize::from(r)] += 1;
+                let r = fast_b_mapping::<EFF_BUCKETS>(
+                    230,
+                    slide_window[j],
+                    slide_window[j_3],
+                    slide_window[j_4],
+                );
+                a_bucket[usize::from(r)] += 1;
+            }
+            fed_len += 1;
+            j = (j + 1) % RNG_SIZE;
+        }
+
+        a_bucket
+    }
+
+    /// Snapshot the builder's in-progress state for later serialization.
+    ///
+    /// Useful for checkpointing a long-running hash of a huge file: persist
+    /// the returned [`BuilderState`] and resume with
+    /// [`from_state`](Self::from_state) after a restart, without re-reading
+    /// the bytes already fed in.
+    pub fn to_state(&self) -> BuilderState<TLSH_CHECKSUM_LEN> {
+        BuilderState {
+            a_bucket: self.a_bucket,
+            slide_window: self.slide_window,
+            checksum: self.checksum,
+            data_len: self.data_len as u64,
+            min_nonzero_percent: self.min_nonzero_percent,
+        }
+    }
+
+    /// Resume a builder from a previously captured [`BuilderState`].
+    pub fn from_state(state: BuilderState<TLSH_CHECKSUM_LEN>) -> Self {
+        Self {
+            a_bucket: state.a_bucket,
+            slide_window: state.slide_window,
+            checksum: state.checksum,
+            data_len: state.data_len as usize,
+            min_nonzero_percent: state.min_nonzero_percent,
+        }
+    }
+
+    /// Merge another builder's accumulated bucket counts into this one.
+    ///
+    /// This elementwise-adds `a_bucket` and sums `data_len`, for the common
+    /// case where `self` and `other` cover two halves of the same
+    /// concatenated stream and the caller has preserved window continuity
+    /// across the split (e.g. by feeding the trailing few bytes of the
+    /// first half into the second before hashing it). `checksum` and
+    /// `slide_window` aren't meaningful to merge this way and are left as
+    /// `self`'s.
+    pub fn merge(&mut self, other: &Self) {
+        for (a, b) in self.a_bucket.iter_mut().zip(other.a_bucket.iter()) {
+            *a += b;
+        }
+        self.data_len += other.data_len;
+    }
+
+    /// Override the "buckets must be non-zero" acceptance ratio used by
+    /// [`build`](Self::build) and [`try_build`](Self::try_build).
+    ///
+    /// `percent` is out of 100 and is clamped to `0..=100`: the hash is only
+    /// accepted if more than this percentage of the first `CODE_SIZE * 5`
+    /// buckets are non-zero. If never called, the default is 50% (40% when
+    /// `EFF_BUCKETS == 48`, matching upstream TLSH's special case for that
+    /// configuration).
+    pub fn set_min_nonzero_percent(&mut self, percent: u8) {
+        self.min_nonzero_percent = Some(percent.min(100));
+    }
+
     /// Generate a [`Tlshx`] object, or None if the object is not valid.
     pub fn build(&self) -> Option<Tlshx<TLSH_CHECKSUM_LEN, TLSH_STRING_LEN_REQ, CODE_SIZE>> {
+        self.try_build().ok()
+    }
+
+    /// Generate a [`Tlshx`] object, or an error describing why it couldn't be built.
+    pub fn try_build(
+        &self,
+    ) -> Result<Tlshx<TLSH_CHECKSUM_LEN, TLSH_STRING_LEN_REQ, CODE_SIZE>, BuildError> {
         if self.data_len < MIN_DATA_LENGTH {
-            return None;
+            return Err(BuildError::TooShort);
         }
 
         let (q1, q2) = get_tertiles::<EFF_BUCKETS>(&self.a_bucket);
         // issue #79 - divide by 0 if q2 == 0
         if q2 == 0 {
-            return None;
+            return Err(BuildError::ZeroQuartile);
         }
 
-        // buckets must be more than 50% non-zero
-        let nonzero = self
-            .a_bucket
-            .iter()
-            .take(CODE_SIZE * 5)
-            .filter(|v| **v > 0)
-            .count();
-        // TODO: Special case EFF_BUCKETS == 48
-        if nonzero * 2 <= 5 * CODE_SIZE {
-            return None;
+        // buckets must be more than `min_nonzero_percent`% non-zero
+        let total = CODE_SIZE * 5;
+        let nonzero = self.a_bucket.iter().take(total).filter(|v| **v > 0).count();
+        let percent = usize::from(
+            self.min_nonzero_percent
+                .unwrap_or(default_min_nonzero_percent(EFF_BUCKETS)),
+        );
+        if nonzero * 100 <= total * percent {
+            let required = total * percent / 100 + 1;
+            return Err(BuildError::TooSparse { nonzero, required });
         }
 
         let mut code: [u8; CODE_SIZE] = [0; CODE_SIZE];
@@ -210,7 +456,7 @@ impl<
         let lvalue = l_capturing(self.data_len as u32);
         let q1_ratio = (((((q1 * 100) as f32) / (q2 as f32)) as u32) % 16) as u8;
 
-        Some(Tlshx {
+        Ok(Tlshx {
             lvalue,
             q1_ratio,
             checksum: self.checksum,
@@ -219,6 +465,111 @@ impl<
     }
 }
 
+/// Reasons [`TlshxBuilder::try_build`] can fail to produce a [`Tlshx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildError {
+    /// Fewer than `MIN_DATA_LENGTH` bytes were fed to the builder.
+    TooShort,
+    /// The upper tertile bucket count (`q2`) is zero, so the `q1 / q2` ratio
+    /// can't be computed (see issue #79).
+    ZeroQuartile,
+    /// No more than the configured ratio of buckets are non-zero, which usually
+    /// indicates low-entropy or repetitive input.
+    TooSparse {
+        /// Number of non-zero buckets observed.
+        nonzero: usize,
+        /// Minimum number of non-zero buckets required to accept the hash.
+        required: usize,
+    },
+}
+
+/// A snapshot of a [`TlshxBuilder`]'s in-progress state.
+///
+/// Produced by [`TlshxBuilder::to_state`] and consumed by
+/// [`TlshxBuilder::from_state`], this lets a long-running service checkpoint
+/// partial hashing of a huge input and resume after a restart, or merge two
+/// independently-computed partial states with
+/// [`TlshxBuilder::merge`](TlshxBuilder::merge). The layout is a fixed-size
+/// sequence of little-endian integers (see [`encode`](Self::encode) /
+/// [`decode`](Self::decode)), so it's `no_std`-friendly and safe to store as
+/// opaque bytes across process boundaries.
+#[derive(Clone, Copy)]
+pub struct BuilderState<const TLSH_CHECKSUM_LEN: usize> {
+    a_bucket: [u32; BUCKETS],
+    slide_window: [u8; SLIDING_WND_SIZE],
+    checksum: [u8; TLSH_CHECKSUM_LEN],
+    data_len: u64,
+    min_nonzero_percent: Option<u8>,
+}
+
+impl<const TLSH_CHECKSUM_LEN: usize> BuilderState<TLSH_CHECKSUM_LEN> {
+    /// Number of bytes occupied by the [`encode`](Self::encode)d layout.
+    pub const ENCODED_LEN: usize = BUCKETS * 4 + SLIDING_WND_SIZE + TLSH_CHECKSUM_LEN + 8 + 1;
+
+    /// Write the fixed-size byte layout to `out`.
+    ///
+    /// Returns `None` if `out` is shorter than [`ENCODED_LEN`](Self::ENCODED_LEN).
+    pub fn encode(&self, out: &mut [u8]) -> Option<()> {
+        if out.len() < Self::ENCODED_LEN {
+            return None;
+        }
+
+        let mut i = 0;
+        for v in &self.a_bucket {
+            out[i..i + 4].copy_from_slice(&v.to_le_bytes());
+            i += 4;
+        }
+        out[i..i + SLIDING_WND_SIZE].copy_from_slice(&self.slide_window);
+        i += SLIDING_WND_SIZE;
+        out[i..i + TLSH_CHECKSUM_LEN].copy_from_slice(&self.checksum);
+        i += TLSH_CHECKSUM_LEN;
+        out[i..i + 8].copy_from_slice(&self.data_len.to_le_bytes());
+        i += 8;
+        // 0 means "unset"; a configured percent is stored offset by 1 so it
+        // never collides with that sentinel.
+        out[i] = self.min_nonzero_percent.map_or(0, |p| p + 1);
+
+        Some(())
+    }
+
+    /// Read back a state previously written by [`encode`](Self::encode).
+    ///
+    /// Returns `None` if `bytes` is shorter than [`ENCODED_LEN`](Self::ENCODED_LEN).
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < Self::ENCODED_LEN {
+            return None;
+        }
+
+        let mut i = 0;
+        let mut a_bucket = [0u32; BUCKETS];
+        for v in a_bucket.iter_mut() {
+            *v = u32::from_le_bytes(bytes[i..i + 4].try_into().ok()?);
+            i += 4;
+        }
+
+        let mut slide_window = [0u8; SLIDING_WND_SIZE];
+        slide_window.copy_from_slice(&bytes[i..i + SLIDING_WND_SIZE]);
+        i += SLIDING_WND_SIZE;
+
+        let mut checksum = [0u8; TLSH_CHECKSUM_LEN];
+        checksum.copy_from_slice(&bytes[i..i + TLSH_CHECKSUM_LEN]);
+        i += TLSH_CHECKSUM_LEN;
+
+        let data_len = u64::from_le_bytes(bytes[i..i + 8].try_into().ok()?);
+        i += 8;
+
+        let min_nonzero_percent = bytes[i].checked_sub(1);
+
+        Some(Self {
+            a_bucket,
+            slide_window,
+            checksum,
+            data_len,
+            min_nonzero_percent,
+        })
+    }
+}
+
 /// TLSHX object, from which a hash or a distance can be computed.
 pub struct Tlshx<
     const TLSH_CHECKSUM_LEN: usize,
@@ -383,3 +734,146 @@ impl<const TLSH_CHECKSUM_LEN: usize, const TLSH_STRING_LEN_REQ: usize, const COD
         Self::from_hash(s.as_bytes()).ok_or(ParseError)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Matches the standard (non-TLSHX "strong") configuration used by
+    // `TlshxDefaultBuilder` elsewhere in the crate: 128 effective buckets,
+    // a 1-byte checksum, a 32-byte code, and the `TX`-prefixed 72-char hash.
+    type TestBuilder = TlshxBuilder<128, 1, 32, 72, 50>;
+
+    #[cfg(feature = "threaded")]
+    fn assert_parallel_matches_serial(data: &[u8]) {
+        let mut serial = TestBuilder::new();
+        serial.update(data);
+
+        let mut parallel = TestBuilder::new();
+        parallel.update_parallel(data);
+
+        assert_eq!(serial.a_bucket, parallel.a_bucket);
+        assert_eq!(serial.checksum, parallel.checksum);
+        assert_eq!(serial.data_len, parallel.data_len);
+    }
+
+    #[cfg(feature = "threaded")]
+    #[test]
+    fn update_parallel_matches_serial_update() {
+        // Below the `2 * THREAD_CHUNK_SIZE` threshold: takes the serial fallback.
+        assert_parallel_matches_serial(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit");
+
+        // A handful of full chunks with no trailing partial chunk.
+        let exact_chunks = vec![0x5A_u8; 3 * THREAD_CHUNK_SIZE];
+        assert_parallel_matches_serial(&exact_chunks);
+
+        // Full chunks plus a short trailing partial chunk.
+        let mut with_trailer = vec![0x5A_u8; 3 * THREAD_CHUNK_SIZE];
+        with_trailer.extend(core::iter::repeat_n(0xA5_u8, 37));
+        assert_parallel_matches_serial(&with_trailer);
+    }
+
+    fn state_round_trip(state: &BuilderState<1>) -> BuilderState<1> {
+        let mut bytes = [0u8; BuilderState::<1>::ENCODED_LEN];
+        state
+            .encode(&mut bytes)
+            .expect("buffer is exactly ENCODED_LEN");
+        BuilderState::decode(&bytes).expect("just-encoded bytes must decode")
+    }
+
+    #[test]
+    fn builder_state_round_trips_through_encode_decode() {
+        let mut builder = TestBuilder::new();
+        builder.update(b"Lorem ipsum dolor sit amet, consectetur adipiscing elit");
+
+        // No override set (`None`).
+        let state = builder.to_state();
+        let decoded = state_round_trip(&state);
+        assert_eq!(decoded.a_bucket, builder.a_bucket);
+        assert_eq!(decoded.checksum, builder.checksum);
+        assert_eq!(decoded.data_len, builder.data_len as u64);
+        assert_eq!(decoded.min_nonzero_percent, None);
+
+        // A representative override, and the two boundary values.
+        for percent in [0_u8, 37, 100] {
+            builder.set_min_nonzero_percent(percent);
+            let decoded = state_round_trip(&builder.to_state());
+            assert_eq!(decoded.min_nonzero_percent, Some(percent));
+        }
+    }
+
+    #[test]
+    fn builder_state_decode_rejects_short_buffers() {
+        let bytes = [0u8; BuilderState::<1>::ENCODED_LEN - 1];
+        assert!(BuilderState::<1>::decode(&bytes).is_none());
+    }
+
+    const LOREM: &[u8] = b"Lorem ipsum dolor sit amet, consectetur adipiscing elit";
+
+    // `Tlshx` doesn't derive `Debug`, so `try_build`'s `Ok` side is matched
+    // manually below rather than via `unwrap_err`/`expect_err`.
+    fn err_of<T>(result: Result<T, BuildError>) -> BuildError {
+        match result {
+            Err(e) => e,
+            Ok(_) => panic!("expected an Err"),
+        }
+    }
+
+    #[test]
+    fn try_build_reports_too_short() {
+        let mut builder = TestBuilder::new();
+        builder.update(&LOREM[..10]);
+        assert_eq!(err_of(builder.try_build()), BuildError::TooShort);
+    }
+
+    #[test]
+    fn try_build_reports_zero_quartile() {
+        // A long run of a single repeated byte only ever touches a handful
+        // of distinct buckets (one per triple salt), so the upper tertile
+        // `q2` stays at zero regardless of the underlying pearson tables.
+        let mut builder = TestBuilder::new();
+        builder.update(&[0x41; 64]);
+        assert_eq!(err_of(builder.try_build()), BuildError::ZeroQuartile);
+    }
+
+    #[test]
+    fn try_build_reports_too_sparse_and_honors_override() {
+        let mut builder = TestBuilder::new();
+        builder.update(LOREM);
+
+        // At the default threshold this is the same input used in the
+        // crate's own hash/diff doctests, so it must succeed.
+        assert!(builder.try_build().is_ok());
+
+        // Demanding every single bucket be non-zero is unreachable for this
+        // input, so the override should now reject it with a diagnostic.
+        builder.set_min_nonzero_percent(100);
+        match err_of(builder.try_build()) {
+            BuildError::TooSparse { nonzero, required } => assert!(nonzero < required),
+            other => panic!("expected TooSparse, got {other:?}"),
+        }
+
+        // Loosening the ratio all the way down must accept again.
+        builder.set_min_nonzero_percent(0);
+        assert!(builder.try_build().is_ok());
+    }
+
+    #[test]
+    fn default_min_nonzero_percent_special_cases_48_buckets() {
+        assert_eq!(default_min_nonzero_percent(48), 40);
+        assert_eq!(default_min_nonzero_percent(128), 50);
+        assert_eq!(default_min_nonzero_percent(256), 50);
+    }
+
+    // No type alias in this crate currently instantiates `EFF_BUCKETS == 48`,
+    // so exercise the configuration directly to confirm `try_build` runs
+    // (and doesn't panic) for it rather than leaving the branch dead code.
+    type Test48Builder = TlshxBuilder<48, 1, 9, 26, 50>;
+
+    #[test]
+    fn try_build_runs_for_48_bucket_configuration() {
+        let mut builder = Test48Builder::new();
+        builder.update(LOREM);
+        let _ = builder.try_build();
+    }
+}